@@ -0,0 +1,121 @@
+// Copyright (c) 2020 kprotty
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A classic MCS queue lock: each waiter spins on its own cache line instead of a
+//! shared one, so it stays scalable and FIFO under contention. Nodes are heap
+//! allocated and handed between threads, so they're freed through `util::epoch`
+//! rather than immediately, since a releasing thread can't otherwise tell when a
+//! successor has finished reading its node.
+
+use super::util::{epoch, SpinWait};
+use crate::utils::sync::{AtomicBool, AtomicPtr, Ordering};
+use std::ptr::{null_mut, NonNull};
+
+struct Node {
+    next: AtomicPtr<Node>,
+    locked: AtomicBool,
+}
+
+pub struct Lock {
+    tail: AtomicPtr<Node>,
+}
+
+unsafe impl super::Lock for Lock {
+    const NAME: &'static str = "mcs_lock";
+
+    fn new() -> Self {
+        Self {
+            tail: AtomicPtr::new(null_mut()),
+        }
+    }
+
+    fn with(&self, f: impl FnOnce()) {
+        let node = self.acquire();
+        f();
+        self.release(node);
+    }
+}
+
+impl Lock {
+    fn acquire(&self) -> NonNull<Node> {
+        let node = Box::into_raw(Box::new(Node {
+            next: AtomicPtr::new(null_mut()),
+            locked: AtomicBool::new(true),
+        }));
+
+        let prev = self.tail.swap(node, Ordering::AcqRel);
+        if !prev.is_null() {
+            unsafe { (*prev).next.store(node, Ordering::Release) };
+
+            let mut spin = SpinWait::new();
+            while unsafe { (*node).locked.load(Ordering::Acquire) } {
+                if !spin.yield_now() {
+                    spin.reset();
+                }
+            }
+        }
+
+        unsafe { NonNull::new_unchecked(node) }
+    }
+
+    fn release(&self, node: NonNull<Node>) {
+        let node = node.as_ptr();
+        let _guard = epoch::pin();
+
+        unsafe {
+            if (*node).next.load(Ordering::Acquire).is_null() {
+                if self
+                    .tail
+                    .compare_exchange(node, null_mut(), Ordering::AcqRel, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    return epoch::retire(node);
+                }
+
+                let mut spin = SpinWait::new();
+                loop {
+                    let next = (*node).next.load(Ordering::Acquire);
+                    if !next.is_null() {
+                        (*next).locked.store(false, Ordering::Release);
+                        break;
+                    }
+                    if !spin.yield_now() {
+                        spin.reset();
+                    }
+                }
+            } else {
+                let next = (*node).next.load(Ordering::Acquire);
+                (*next).locked.store(false, Ordering::Release);
+            }
+
+            epoch::retire(node);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "loom"))]
+mod tests {
+    use super::Lock;
+
+    #[test]
+    fn serializes_critical_sections() {
+        // Stays at 1 round: unlike the other locks here, mcs_lock's two
+        // unbounded spin points (acquire's wait on `locked`, release's wait
+        // for a visible successor) make loom's exhaustive search blow up
+        // combinatorially the moment a thread re-enters `with(...)` - even a
+        // single extra critical section pushed this well past what's
+        // practical to run routinely.
+        crate::util::loom_check::assert_mutual_exclusion::<Lock>(2, 1);
+    }
+}
@@ -13,44 +13,135 @@
 // limitations under the License.
 
 use std::{
+    env,
+    sync::{atomic::{AtomicBool, Ordering}, Arc},
     thread,
-    env
+    time::{Duration, Instant},
 };
 
-fn bench_all(b: &mut Benchmarker) {
+#[path = "../../src/utils/mod.rs"]
+mod utils;
 
+mod flume_lock;
+mod mcs_lock;
+mod safe_parker;
+mod spin_lock;
+mod util;
+mod worker_lock;
+
+use util::Rng;
+
+/// A lock implementation the benchmark harness can drive.
+///
+/// # Safety
+/// `with` must provide mutual exclusion: at most one closure passed to `with`
+/// may be running at a time across all threads sharing a given instance.
+pub(crate) unsafe trait Lock: Send + Sync {
+    const NAME: &'static str;
+
+    fn new() -> Self;
+
+    fn with(&self, f: impl FnOnce());
 }
 
-pub fn main() {
+/// A single `csv-ranged` term: either a fixed value, or a `lo-hi` range that's
+/// resolved to a fresh random value on every trial.
+#[derive(Copy, Clone, Debug)]
+enum Term<T> {
+    Value(T),
+    Range(T, T),
+}
 
+impl Term<usize> {
+    fn resolve(&self, rng: &mut Rng) -> usize {
+        match *self {
+            Term::Value(value) => value,
+            Term::Range(lo, hi) => rng.range_usize(lo, hi),
+        }
+    }
+}
+
+impl Term<Duration> {
+    fn resolve(&self, rng: &mut Rng) -> Duration {
+        match *self {
+            Term::Value(value) => value,
+            Term::Range(lo, hi) => {
+                let nanos = rng.range_u128(lo.as_nanos(), hi.as_nanos());
+                Duration::from_nanos(nanos as u64)
+            }
+        }
+    }
 }
 
 struct Parser;
 
 impl Parser {
-    fn parse<T>(
-        input: Option<String>,
-        resolve: impl FnMut(&mut Vec<T>, (u64, Option<u64>), Option<(u64, Option<u64>)>),
-    ) -> Vec<T> {
-        let mut results = Vec::new();
-        let input = input.unwrap_or_else(|| Self::error("invalid argument"));
-        let mut input = input.chars().peekable(); 
-
-        let mut parse_value = || {
-            let mut value = None;
-            while let Some(&c) = input.peek() {
-                if c > '0
-            }
+    fn parse_counts(input: Option<&str>, default: &str) -> Vec<Term<usize>> {
+        Self::parse_terms(input, default, |s| {
+            s.parse::<usize>()
+                .unwrap_or_else(|_| Self::error("invalid [count]"))
+        })
+    }
+
+    fn parse_durations(input: Option<&str>, default: &str) -> Vec<Term<Duration>> {
+        Self::parse_terms(input, default, Self::parse_duration)
+    }
+
+    fn parse_duration(term: &str) -> Duration {
+        let split_at = term
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or_else(|| Self::error("missing [time_unit] on [time]"));
+        let (digits, unit) = term.split_at(split_at);
+
+        let value: u128 = digits
+            .parse()
+            .unwrap_or_else(|_| Self::error("invalid [time]"));
+
+        let nanos = match unit {
+            "ns" => value,
+            "us" => value * 1_000,
+            "ms" => value * 1_000_000,
+            "s" => value * 1_000_000_000,
+            _ => Self::error("invalid [time_unit]: expected \"ns\", \"us\", \"ms\", or \"s\""),
         };
 
-        loop {
-            let first = Self::parse_value(&mut )
-        }
+        Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+    }
+
+    /// Parses a `csv-ranged` argument: a comma-separated list of terms, each
+    /// either a bare value or a `lo-hi` range, converting each side through
+    /// `parse_value`.
+    fn parse_terms<T: Copy + PartialOrd>(
+        input: Option<&str>,
+        default: &str,
+        parse_value: impl Fn(&str) -> T,
+    ) -> Vec<Term<T>> {
+        let input = input.unwrap_or(default);
 
-        results
+        input
+            .split(',')
+            .map(|term| {
+                let term = term.trim();
+                if term.is_empty() {
+                    Self::error("empty [csv-ranged] term");
+                }
+
+                match term.find('-') {
+                    None => Term::Value(parse_value(term)),
+                    Some(dash) => {
+                        let lo = parse_value(term[..dash].trim());
+                        let hi = parse_value(term[dash + 1..].trim());
+                        if lo > hi {
+                            Self::error("[csv-ranged] range has lo > hi");
+                        }
+                        Term::Range(lo, hi)
+                    }
+                }
+            })
+            .collect()
     }
 
-    fn error(message: &'static str) -> ! {
+    fn error(message: &str) -> ! {
         eprintln!("Error: {:?}\n", message);
         Self::print_help(std::env::args().next().unwrap());
         std::process::exit(1)
@@ -79,4 +170,125 @@ impl Parser {
         );
         println!();
     }
-}
\ No newline at end of file
+}
+
+fn thread_seed() -> u64 {
+    use std::sync::atomic::AtomicU64;
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let local = 0u8;
+    let addr = &local as *const u8 as u64;
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    addr ^ count.wrapping_mul(0x9E3779B97F4A7C15)
+}
+
+fn busy_wait(duration: Duration) {
+    if duration.is_zero() {
+        return;
+    }
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        std::hint::spin_loop();
+    }
+}
+
+fn run<L: Lock + 'static>(
+    measure: Duration,
+    threads: usize,
+    locked: Term<Duration>,
+    unlocked: Term<Duration>,
+) {
+    let lock = Arc::new(L::new());
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = lock.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut rng = Rng::new(thread_seed());
+                let mut latencies_ns = Vec::new();
+
+                while !stop.load(Ordering::Relaxed) {
+                    let locked_for = locked.resolve(&mut rng);
+                    let unlocked_for = unlocked.resolve(&mut rng);
+
+                    let start = Instant::now();
+                    lock.with(|| busy_wait(locked_for));
+                    latencies_ns.push(start.elapsed().as_nanos() as u64);
+
+                    busy_wait(unlocked_for);
+                }
+
+                latencies_ns
+            })
+        })
+        .collect();
+
+    thread::sleep(measure);
+    stop.store(true, Ordering::Relaxed);
+
+    let mut latencies_ns: Vec<u64> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+    latencies_ns.sort_unstable();
+
+    print_row(L::NAME, &latencies_ns, measure);
+}
+
+fn print_row(name: &str, latencies_ns: &[u64], measure: Duration) {
+    if latencies_ns.is_empty() {
+        println!("{:<16} (no samples)", name);
+        return;
+    }
+
+    let count = latencies_ns.len();
+    let sum: u64 = latencies_ns.iter().sum();
+    let mean_ns = sum / count as u64;
+    let median_ns = latencies_ns[count / 2];
+    let p99_ns = latencies_ns[(count * 99 / 100).min(count - 1)];
+    let throughput = count as f64 / measure.as_secs_f64();
+
+    println!(
+        "{:<16} {:>12.0} ops/s  mean {:>8}ns  median {:>8}ns  p99 {:>8}ns",
+        name, throughput, mean_ns, median_ns, p99_ns
+    );
+}
+
+fn bench_all() {
+    let mut args = env::args().skip(1);
+
+    let measures = Parser::parse_durations(args.next().as_deref(), "5s");
+    let threads = Parser::parse_counts(args.next().as_deref(), "1,2,4,8");
+    let lockeds = Parser::parse_durations(args.next().as_deref(), "0ns");
+    let unlockeds = Parser::parse_durations(args.next().as_deref(), "0ns");
+
+    for measure in &measures {
+        for threads in &threads {
+            for locked in &lockeds {
+                for unlocked in &unlockeds {
+                    let mut rng = Rng::new(thread_seed());
+                    let measure = measure.resolve(&mut rng);
+                    let threads = threads.resolve(&mut rng);
+
+                    println!(
+                        "measure={:?} threads={} locked={:?} unlocked={:?}",
+                        measure, threads, locked, unlocked
+                    );
+
+                    run::<spin_lock::Lock>(measure, threads, *locked, *unlocked);
+                    run::<flume_lock::Lock>(measure, threads, *locked, *unlocked);
+                    run::<worker_lock::Lock>(measure, threads, *locked, *unlocked);
+                    run::<safe_parker::Lock>(measure, threads, *locked, *unlocked);
+                    run::<mcs_lock::Lock>(measure, threads, *locked, *unlocked);
+                    println!();
+                }
+            }
+        }
+    }
+}
+
+pub fn main() {
+    bench_all();
+}
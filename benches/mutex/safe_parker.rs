@@ -31,13 +31,17 @@ unsafe impl super::Lock for Lock {
 ///////////////////////////////////////////////////////////////////////////////
 
 use std::{
+    mem,
     mem::drop,
     ops::{Deref, DerefMut},
-    thread::{self, Thread},
     time::{Instant, Duration},
     collections::VecDeque,
-    sync::{Arc, Mutex as StdMutex, MutexGuard as StdMutexGuard},
-    sync::atomic::{AtomicU8, AtomicBool, spin_loop_hint, Ordering},
+};
+#[cfg(not(feature = "loom"))]
+use std::thread;
+use crate::utils::{
+    parker::{self, Thread},
+    sync::{AtomicU8, AtomicBool, spin_loop_hint, Ordering, Arc, Mutex as StdMutex, MutexGuard as StdMutexGuard},
 };
 
 struct Waiter {
@@ -107,11 +111,52 @@ impl<T> Mutex<T> {
             .compare_exchange_weak(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
             .is_err()
         {
-            self.lock_slow();
+            let acquired = self.lock_slow(None);
+            debug_assert!(acquired, "lock_slow() with no deadline must always succeed");
         }
         self.locked()
     }
 
+    /// Like [`Mutex::lock`], but gives up and returns `None` after `timeout` has
+    /// elapsed without acquiring the lock.
+    #[inline]
+    #[allow(unused)]
+    pub fn try_lock_for(&self, timeout: Duration) -> Option<MutexGuard<'_, T>> {
+        self.try_lock_until(Instant::now() + timeout)
+    }
+
+    /// Like [`Mutex::lock`], but gives up and returns `None` once `deadline` has
+    /// passed without acquiring the lock.
+    #[allow(unused)]
+    pub fn try_lock_until(&self, deadline: Instant) -> Option<MutexGuard<'_, T>> {
+        if self.try_lock_fast() {
+            return Some(self.locked());
+        }
+
+        // The deadline is already in the past: do a single non-blocking check
+        // rather than entering the spin/park machinery below.
+        if Instant::now() >= deadline {
+            return if self.try_lock_fast() {
+                Some(self.locked())
+            } else {
+                None
+            };
+        }
+
+        if self.lock_slow(Some(deadline)) {
+            Some(self.locked())
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn try_lock_fast(&self) -> bool {
+        self.state
+            .compare_exchange_weak(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
     #[inline]
     fn unlock(&self) {
         if self
@@ -119,12 +164,26 @@ impl<T> Mutex<T> {
             .compare_exchange(LOCKED, UNLOCKED, Ordering::Release, Ordering::Relaxed)
             .is_err()
         {
-            self.unlock_slow();
+            self.unlock_slow(false);
+        }
+    }
+
+    /// Unlocks, forcing the direct fair hand-off path to a waiter (if any) regardless
+    /// of the eventual-fairness schedule `unlock_slow` would otherwise follow.
+    #[inline]
+    fn unlock_fair(&self) {
+        if self
+            .state
+            .compare_exchange(LOCKED, UNLOCKED, Ordering::Release, Ordering::Relaxed)
+            .is_err()
+        {
+            self.unlock_slow(true);
         }
     }
 
+    /// Returns `true` once the lock is held, `false` if `deadline` passed first.
     #[cold]
-    fn lock_slow(&self) {
+    fn lock_slow(&self, deadline: Option<Instant>) -> bool {
         let mut spin = 0;
         let mut waiter = None;
         let mut state = self.state.load(Ordering::Relaxed);
@@ -137,18 +196,25 @@ impl<T> Mutex<T> {
                     Ordering::Acquire,
                     Ordering::Relaxed,
                 ) {
-                    Ok(_) => return,
+                    Ok(_) => return true,
                     Err(e) => state = e,
                 }
                 continue;
             }
 
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    return false;
+                }
+            }
+
             if state & PARKED == 0 {
                 if spin < 5 {
                     spin += 1;
                     if spin <= 3 {
                         (0..(1 << spin)).for_each(|_| spin_loop_hint());
                     } else {
+                        #[cfg(not(feature = "loom"))]
                         thread::sleep(Duration::from_nanos(1 << spin));
                     }
                     state = self.state.load(Ordering::Relaxed);
@@ -177,20 +243,41 @@ impl<T> Mutex<T> {
                     waiter = Some(Arc::new(Waiter {
                         acquired: AtomicBool::new(false),
                         notified: AtomicBool::new(false),
-                        thread: thread::current(),
+                        thread: parker::current(),
                     }));
                 };
 
                 waiter_ref.notified.store(false, Ordering::Relaxed);
                 queue.waiters.push_back(waiter_ref.clone());
                 drop(queue);
-                
+
+                let timed_out = loop {
+                    if waiter_ref.notified.load(Ordering::Acquire) {
+                        break false;
+                    }
+
+                    match deadline {
+                        None => parker::park(),
+                        Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                            Some(remaining) => parker::park_timeout(remaining),
+                            None => break true,
+                        },
+                    }
+                };
+
+                if timed_out && self.remove_waiter(waiter_ref) {
+                    return false;
+                }
+
+                // Either we were notified, or we timed out but `unlock_slow` had
+                // already popped us before we could remove ourselves: wait for the
+                // handoff it already committed to rather than losing the token.
                 while !waiter_ref.notified.load(Ordering::Acquire) {
-                    thread::park();
+                    parker::park();
                 }
 
                 if waiter_ref.acquired.load(Ordering::Relaxed) {
-                    return;
+                    return true;
                 }
             }
 
@@ -199,32 +286,53 @@ impl<T> Mutex<T> {
         }
     }
 
+    /// Removes `waiter` from the queue if it's still there, clearing `PARKED` when
+    /// that empties the queue. Returns `false` if `unlock_slow` already popped it to
+    /// hand off the lock, in which case the waiter must wait for that notification.
+    fn remove_waiter(&self, waiter: &Arc<Waiter>) -> bool {
+        let mut queue = self.queue.lock().unwrap();
+
+        let position = queue.waiters.iter().position(|w| Arc::ptr_eq(w, waiter));
+        let removed = position.is_some();
+        if let Some(index) = position {
+            queue.waiters.remove(index);
+        }
+
+        if removed && queue.waiters.is_empty() {
+            let _ = self.state.fetch_and(!PARKED, Ordering::Relaxed);
+        }
+
+        removed
+    }
+
     #[cold]
-    fn unlock_slow(&self) {
+    fn unlock_slow(&self, force_fair: bool) {
         let mut queue = self.queue.lock().unwrap();
 
         let waiter = queue.waiters.pop_front();
         if let Some(waiter) = waiter.as_ref() {
-
-            let be_fair = match queue.times_out {
-                None => {
-                    queue.times_out = Some(Instant::now() + Duration::from_millis(1));
-                    queue.xorshift = (self as *const _ as usize) as u32;
-                    false
-                },
-                Some(times_out) => {
-                    let now = Instant::now();
-                    now > times_out && {
-                        queue.times_out = Some(now + Duration::new(0, {
-                            queue.xorshift ^= queue.xorshift << 13;
-                            queue.xorshift ^= queue.xorshift >> 17;
-                            queue.xorshift ^= queue.xorshift << 5;
-                            queue.xorshift % 1_000_000
-                        }));
-                        true
+            let be_fair = force_fair
+                || match queue.times_out {
+                    None => {
+                        queue.times_out = Some(Instant::now() + Duration::from_millis(1));
+                        queue.xorshift = (self as *const _ as usize) as u32;
+                        false
                     }
-                },
-            };
+                    Some(times_out) => {
+                        let now = Instant::now();
+                        now > times_out && {
+                            queue.times_out = Some(
+                                now + Duration::new(0, {
+                                    queue.xorshift ^= queue.xorshift << 13;
+                                    queue.xorshift ^= queue.xorshift >> 17;
+                                    queue.xorshift ^= queue.xorshift << 5;
+                                    queue.xorshift % 1_000_000
+                                }),
+                            );
+                            true
+                        }
+                    }
+                };
 
             waiter.acquired.store(be_fair, Ordering::Relaxed);
             if be_fair && queue.waiters.len() == 0 {
@@ -258,6 +366,35 @@ impl<'a, T> Drop for MutexGuard<'a, T> {
     }
 }
 
+impl<'a, T> MutexGuard<'a, T> {
+    /// Unlocks, taking the fair hand-off path to the next waiter (if any) rather
+    /// than leaving it to the mutex's own eventual-fairness schedule.
+    #[allow(unused)]
+    pub fn unlock_fair(self) {
+        let mut guard = self;
+        drop(guard.guard.take());
+        guard.mutex.unlock_fair();
+        mem::forget(guard);
+    }
+
+    /// Temporarily unlocks and immediately re-locks using the fair path, giving a
+    /// waiting thread a chance to run. Useful when a long critical section wants
+    /// to yield to other waiters partway through without fully releasing `self`.
+    #[allow(unused)]
+    pub fn bump(&mut self) {
+        if self.mutex.state.load(Ordering::Relaxed) & PARKED == 0 {
+            return;
+        }
+
+        drop(self.guard.take());
+        self.mutex.unlock_fair();
+
+        let mut new_guard = self.mutex.lock();
+        self.guard = new_guard.guard.take();
+        mem::forget(new_guard);
+    }
+}
+
 impl<'a, T> Deref for MutexGuard<'a, T> {
     type Target = T;
 
@@ -272,3 +409,51 @@ impl<'a, T> DerefMut for MutexGuard<'a, T> {
     }
 }
 
+#[cfg(all(test, feature = "loom"))]
+mod tests {
+    use super::Lock;
+
+    #[test]
+    fn serializes_critical_sections() {
+        crate::util::loom_check::assert_mutual_exclusion::<Lock>(2, 2);
+    }
+}
+
+// Real-thread/wall-clock behavior, not just the loom-modeled mutual exclusion
+// above: loom has no notion of timeouts, so the deadline-parking and fair
+// hand-off machinery below can only be exercised against a real scheduler.
+#[cfg(all(test, not(feature = "loom")))]
+mod timing_tests {
+    use super::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn try_lock_for_times_out_while_held() {
+        let mutex = Mutex::new(());
+        let _guard = mutex.lock();
+
+        assert!(mutex.try_lock_for(Duration::from_millis(20)).is_none());
+    }
+
+    #[test]
+    fn unlock_fair_hands_off_to_waiter() {
+        let mutex = Arc::new(Mutex::new(0));
+        let guard = mutex.lock();
+
+        let waiter_mutex = mutex.clone();
+        let handle = thread::spawn(move || {
+            *waiter_mutex.lock() += 1;
+        });
+
+        // Give the spawned thread a chance to register itself as a waiter
+        // before the fair unlock below hands the lock straight to it.
+        thread::sleep(Duration::from_millis(50));
+
+        guard.unlock_fair();
+        handle.join().unwrap();
+
+        assert_eq!(*mutex.lock(), 1);
+    }
+}
+
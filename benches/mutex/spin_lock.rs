@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use super::util::SpinWait;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crate::utils::sync::{AtomicBool, Ordering};
 
 pub struct Lock(AtomicBool);
 
@@ -32,21 +32,31 @@ unsafe impl super::Lock for Lock {
 }
 
 impl Lock {
+    /// Test-and-test-and-set: only attempt the actual CAS once a relaxed load
+    /// suggests the lock is free, rather than swapping unconditionally on
+    /// every retry. Besides being the standard way to avoid needlessly
+    /// invalidating other spinners' cache lines, an unconditional `swap`
+    /// writes on every failed retry, which under `feature = "loom"` gives the
+    /// model checker a fresh memory event to account for on every iteration
+    /// and blows up the number of schedules it must explore; a failed load
+    /// is free to repeat.
     fn acquire(&self) {
-        let mut locked = false;
         let mut spin = SpinWait::new();
 
         loop {
-            if !locked && !self.0.swap(true, Ordering::Acquire) {
+            if self
+                .0
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
                 return;
             }
 
-            if !spin.yield_now() {
-                spin.reset();
-                let _ = spin.yield_now();
+            while self.0.load(Ordering::Relaxed) {
+                if !spin.yield_now() {
+                    spin.reset();
+                }
             }
-
-            locked = self.0.load(Ordering::Relaxed);
         }
     }
 
@@ -54,3 +64,13 @@ impl Lock {
         self.0.store(false, Ordering::Release);
     }
 }
+
+#[cfg(all(test, feature = "loom"))]
+mod tests {
+    use super::Lock;
+
+    #[test]
+    fn serializes_critical_sections() {
+        crate::util::loom_check::assert_mutual_exclusion::<Lock>(2, 2);
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2020 kprotty
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small epoch-based reclamation scheme, loosely modeled on `crossbeam-epoch`, so
+//! lock-free data structures in this crate (e.g. `mcs_lock`) can free heap nodes that
+//! other threads might still be dereferencing without hitting the garbage collector's
+//! thread-safety hazards of plain `Box::from_raw`.
+
+use std::cell::RefCell;
+use std::mem::take;
+// Deliberately `std`'s real atomics/`Mutex`, not `crate::utils::sync`'s
+// loom-substituted re-exports: `GLOBAL_EPOCH`, `REGISTRY`, and `BAGS_GLOBAL`
+// below are `static`s, and loom's types aren't `const`-constructible, so they
+// can't populate one. This reclamation bookkeeping sits behind `mcs_lock`'s own atomics
+// (which are loom-aware) rather than being the state a model-checking test
+// inspects directly.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const UNPINNED: usize = usize::MAX;
+const BAGS: usize = 3;
+
+struct Garbage {
+    ptr: *mut (),
+    reclaim: unsafe fn(*mut ()),
+}
+
+unsafe impl Send for Garbage {}
+
+unsafe fn reclaim<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+static GLOBAL_EPOCH: AtomicUsize = AtomicUsize::new(0);
+static REGISTRY: Mutex<Vec<std::sync::Arc<AtomicUsize>>> = Mutex::new(Vec::new());
+static BAGS_GLOBAL: [Mutex<Vec<Garbage>>; BAGS] = [
+    Mutex::new(Vec::new()),
+    Mutex::new(Vec::new()),
+    Mutex::new(Vec::new()),
+];
+
+thread_local! {
+    static LOCAL: RefCell<Option<std::sync::Arc<AtomicUsize>>> = RefCell::new(None);
+}
+
+fn local_slot() -> std::sync::Arc<AtomicUsize> {
+    LOCAL.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        if let Some(slot) = cell.as_ref() {
+            return slot.clone();
+        }
+        let slot = std::sync::Arc::new(AtomicUsize::new(UNPINNED));
+        REGISTRY.lock().unwrap().push(slot.clone());
+        *cell = Some(slot.clone());
+        slot
+    })
+}
+
+/// A pin guard publishing this thread's observed epoch for the duration of its lifetime.
+/// Dropping it un-pins the thread.
+pub(crate) struct Guard {
+    slot: std::sync::Arc<AtomicUsize>,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.slot.store(UNPINNED, Ordering::Release);
+    }
+}
+
+/// Publishes the calling thread's current epoch so `try_advance` knows not to free
+/// anything it might still be dereferencing, then opportunistically tries to advance
+/// the global epoch.
+pub(crate) fn pin() -> Guard {
+    let slot = local_slot();
+    let epoch = GLOBAL_EPOCH.load(Ordering::Relaxed);
+    slot.store(epoch, Ordering::Release);
+    try_advance(epoch);
+    Guard { slot }
+}
+
+/// Advances the global epoch only once every pinned thread has observed it, then frees
+/// the garbage bag from two epochs back.
+fn try_advance(epoch: usize) {
+    let registry = REGISTRY.lock().unwrap();
+    for slot in registry.iter() {
+        let pinned = slot.load(Ordering::Acquire);
+        if pinned != UNPINNED && pinned != epoch {
+            return;
+        }
+    }
+    drop(registry);
+
+    if GLOBAL_EPOCH
+        .compare_exchange(epoch, epoch + 1, Ordering::AcqRel, Ordering::Relaxed)
+        .is_err()
+    {
+        return;
+    }
+
+    let freed_bag = (epoch + 1 + BAGS - 2) % BAGS;
+    let garbage = take(&mut *BAGS_GLOBAL[freed_bag].lock().unwrap());
+    for garbage in garbage {
+        unsafe { (garbage.reclaim)(garbage.ptr) };
+    }
+}
+
+/// Defers freeing `ptr` until no pinned thread could still be observing it.
+///
+/// # Safety
+/// `ptr` must have been allocated with `Box::new` and must not be accessed again after
+/// this call.
+pub(crate) unsafe fn retire<T>(ptr: *mut T) {
+    let epoch = GLOBAL_EPOCH.load(Ordering::Relaxed);
+    let bag = epoch % BAGS;
+    BAGS_GLOBAL[bag].lock().unwrap().push(Garbage {
+        ptr: ptr as *mut (),
+        reclaim: reclaim::<T>,
+    });
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2020 kprotty
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal xorshift64 PRNG so ranged benchmark parameters don't need a `rand` dependency.
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform value in `[lo, hi]` inclusive.
+    pub(crate) fn range_u128(&mut self, lo: u128, hi: u128) -> u128 {
+        if lo >= hi {
+            return lo;
+        }
+        let span = hi - lo + 1;
+        let bits = (self.next_u64() as u128) | ((self.next_u64() as u128) << 64);
+        lo + (bits % span)
+    }
+
+    pub(crate) fn range_usize(&mut self, lo: usize, hi: usize) -> usize {
+        self.range_u128(lo as u128, hi as u128) as usize
+    }
+}
@@ -32,10 +32,13 @@ impl SpinWait {
         if self.0 <= 3 {
             (0..(1 << self.0)).for_each(|_| std::sync::atomic::spin_loop_hint());
         } else {
-            #[cfg(windows)]
-            unsafe { super::sys::Sleep(0) };
-            #[cfg(not(windows))]
-            std::thread::yield_now();
+            // `crate::utils::parker::yield_now`, not `std::thread::yield_now`
+            // directly: under loom this spin loop runs as one of its
+            // cooperatively-scheduled coroutines, and a real OS yield doesn't
+            // hand control back to loom's scheduler the way its own
+            // `yield_now` does, so loom would spin the same "thread"
+            // indefinitely within a single explored schedule.
+            crate::utils::parker::yield_now();
         }
 
         true
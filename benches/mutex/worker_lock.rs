@@ -13,15 +13,18 @@
 // limitations under the License.
 
 use std::{
-    thread,
     ptr::{NonNull, null_mut},
     cell::Cell,
     mem::transmute,
-    sync::atomic::{AtomicUsize, AtomicPtr, AtomicBool, Ordering},
+};
+use super::util::epoch;
+use crate::utils::{
+    parker::{self, Thread},
+    sync::{AtomicUsize, AtomicPtr, AtomicBool, Ordering},
 };
 
 struct Event {
-    thread: Cell<Option<thread::Thread>>,
+    thread: Cell<Option<Thread>>,
     is_set: AtomicBool,
 }
 
@@ -59,47 +62,75 @@ unsafe impl super::Lock for Lock {
                     }
                 }
 
-                if state == 1 && spin < 100 {
+                // Capped far lower under `feature = "loom"`: every extra
+                // iteration here is another `state.load` for the model
+                // checker to fork a schedule on, and the property under test
+                // (mutual exclusion) doesn't depend on how many times a
+                // waiter spins before registering, only on the registration
+                // and wake-up logic below actually being correct.
+                #[cfg(not(feature = "loom"))]
+                const SPIN_LIMIT: usize = 100;
+                #[cfg(feature = "loom")]
+                const SPIN_LIMIT: usize = 2;
+
+                if state == 1 && spin < SPIN_LIMIT {
                     spin += 1;
                     std::hint::spin_loop();
                     state = self.state.load(Ordering::Relaxed);
                     continue;
                 }
 
-                let waiter = Waiter {
+                // Heap-allocated (not stack-local, as a prior revision had it):
+                // a stack-local `Waiter` gets reused at the same address by every
+                // call this thread makes to `with()`, and the release loop's
+                // trailing CAS below only compares `state` against that raw
+                // address. A thread that registers again after being combined
+                // can land on the exact address the combiner just finished
+                // draining, and the "nothing new happened" CAS would then match
+                // it by accident and strand the new registration forever. Heap
+                // allocation plus `util::epoch` reclamation (the same scheme
+                // `mcs_lock` uses for its nodes) keeps an address from being
+                // reused until no combiner could still be walking the chain that
+                // used to contain it.
+                let waiter = Box::into_raw(Box::new(Waiter {
                     next: Cell::new(NonNull::new((state & !1usize) as *mut Waiter)),
                     func: transmute(&mut f as *mut dyn FnMut()),
                     event: AtomicPtr::new(null_mut()),
-                };
+                }));
 
                 if let Err(e) = self.state.compare_exchange_weak(
                     state,
-                    &waiter as *const Waiter as usize,
+                    waiter as usize,
                     Ordering::Release,
                     Ordering::Relaxed,
                 ) {
                     state = e;
+                    drop(Box::from_raw(waiter));
                     continue;
                 }
 
-                if waiter.event.load(Ordering::Acquire).is_null() {
+                let guard = epoch::pin();
+                if (*waiter).event.load(Ordering::Acquire).is_null() {
                     let event = Event{
-                        thread: Cell::new(Some(thread::current())),
+                        thread: Cell::new(Some(parker::current())),
                         is_set: AtomicBool::new(false),
                     };
-            
-                    if waiter.event.swap(&event as *const Event as *mut Event, Ordering::AcqRel).is_null() {
+
+                    if (*waiter).event.swap(&event as *const Event as *mut Event, Ordering::AcqRel).is_null() {
                         while !event.is_set.load(Ordering::Acquire) {
-                            thread::park();
+                            parker::park();
                         }
                     }
                 }
+                epoch::retire(waiter);
+                drop(guard);
 
                 return;
             }
 
             f();
-            
+
+            let _guard = epoch::pin();
             let mut last = None;
             state = 1;
 
@@ -137,3 +168,12 @@ unsafe impl super::Lock for Lock {
     }
 }
 
+#[cfg(all(test, feature = "loom"))]
+mod tests {
+    use super::Lock;
+
+    #[test]
+    fn serializes_critical_sections() {
+        crate::util::loom_check::assert_mutual_exclusion::<Lock>(2, 2);
+    }
+}
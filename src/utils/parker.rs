@@ -0,0 +1,32 @@
+// Copyright (c) 2020 kprotty
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Thin re-export of thread parking so `loom` can intercept it the same way it
+//! intercepts the atomics in [`super::sync`]. Lock impls should call
+//! `parker::current()` / `parker::park()` / `thread.unpark()` instead of reaching
+//! into `std::thread` directly so they stay model-checkable.
+
+#[cfg(feature = "loom")]
+pub(crate) use loom::thread::{current, park, yield_now, Thread};
+
+#[cfg(not(feature = "loom"))]
+pub(crate) use std::thread::{current, park, park_timeout, yield_now, Thread};
+
+/// loom has no notion of wall-clock time — it exhaustively explores schedules
+/// instead of racing a timer — so it doesn't expose `park_timeout`. Nothing
+/// model-checked exercises the timeout path, so this just parks indefinitely.
+#[cfg(feature = "loom")]
+pub(crate) fn park_timeout(_duration: std::time::Duration) {
+    loom::thread::park();
+}
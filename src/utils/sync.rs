@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Wrappers for [`core::sync`] which allow a central place to substitute platform atomics and shared mutability.
+//! Wrappers for [`core::sync`] which allow a central place to substitute platform atomics
+//! and shared mutability with their `loom` equivalents so every `Lock` impl can be
+//! model-checked. See [`super::parker`] for the analogous substitution of thread parking.
 
 #[cfg(feature = "loom")]
 pub(crate) use loom::{
     cell::UnsafeCell,
-    sync::atomic::{fence, spin_loop_hint, AtomicU8, AtomicUsize, Ordering},
+    sync::{
+        atomic::{fence, spin_loop_hint, AtomicBool, AtomicPtr, AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
 };
 
 #[cfg(not(feature = "loom"))]
@@ -25,11 +30,12 @@ pub(crate) use if_core::*;
 
 #[cfg(not(feature = "loom"))]
 mod if_core {
-    pub(crate) use core::sync::atomic::{fence, spin_loop_hint, Ordering};
+    pub(crate) use core::sync::atomic::{fence, spin_loop_hint, AtomicBool, Ordering};
+    pub(crate) use std::sync::{Arc, Mutex, MutexGuard};
 
     #[cfg_attr(feature = "nightly", cfg(target_has_atomic = "ptr"))]
     #[cfg(target_atomic_usize)]
-    pub(crate) use core::sync::atomic::AtomicUsize;
+    pub(crate) use core::sync::atomic::{AtomicPtr, AtomicUsize};
 
     #[cfg_attr(feature = "nightly", cfg(target_has_atomic = "8"))]
     #[cfg(target_atomic_u8)]